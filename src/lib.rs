@@ -7,7 +7,7 @@
 // Licensed under the PostgreSQL License
 
 use pgrx::prelude::*;
-use pgrx::JsonB;
+use pgrx::{pg_sys, JsonB};
 use serde_json::Value;
 
 // Tell pgrx which PostgreSQL versions we support
@@ -31,6 +31,8 @@ pub mod pg_test {
 mod array_ops;
 mod depth;
 mod merge;
+mod path;
+mod raw_jsonb;
 mod search;
 
 // Re-exports for public API (maintains backward compatibility)
@@ -90,16 +92,146 @@ pub use merge::*;
 #[allow(clippy::needless_pass_by_value)]
 #[pg_extern(immutable, parallel_safe)]
 fn jsonb_extract_id(data: JsonB, key: default!(&str, "'id'")) -> Option<String> {
-    let obj = data.0.as_object()?;
-    let id_value = obj.get(key)?;
+    match extract_as(&data, key, ExtractTarget::AsText)? {
+        ExtractedId::Text(s) => Some(s),
+        ExtractedId::Jsonb(_) | ExtractedId::Numeric(_) | ExtractedId::Bool(_) => None,
+    }
+}
+
+/// Extraction target for ID values — selects how the raw JSON value found
+/// by [`extract_as`] should be converted before being handed back across
+/// the `pg_extern` boundary.
+#[derive(Clone, Copy)]
+enum ExtractTarget {
+    /// Coerce to text: strings as-is, numbers via `to_string()`.
+    AsText,
+    /// Return the raw JSONB sub-value untouched.
+    AsJsonb,
+    /// Return the value in its native Rust representation (`f64`/`bool`).
+    AsNative,
+}
+
+/// Result of converting a raw JSON value to an [`ExtractTarget`].
+enum ExtractedId<'a> {
+    Text(String),
+    Jsonb(&'a Value),
+    Numeric(f64),
+    Bool(bool),
+}
 
-    match id_value {
-        Value::String(s) => Some(s.clone()),
-        Value::Number(n) => Some(n.to_string()),
+/// Look up `key` in `data` and convert it to `target`, if the value found
+/// supports that representation (e.g. `AsNative` on a string yields `None`).
+///
+/// Shared by `jsonb_extract_id` and its typed companions so the
+/// extraction target is chosen at the call site instead of duplicating
+/// the lookup in each function.
+#[inline]
+fn extract_as<'a>(data: &'a JsonB, key: &str, target: ExtractTarget) -> Option<ExtractedId<'a>> {
+    let value = data.0.as_object()?.get(key)?;
+
+    match (target, value) {
+        (ExtractTarget::AsText, Value::String(s)) => Some(ExtractedId::Text(s.clone())),
+        (ExtractTarget::AsText, Value::Number(n)) => Some(ExtractedId::Text(n.to_string())),
+        (ExtractTarget::AsJsonb, v) => Some(ExtractedId::Jsonb(v)),
+        (ExtractTarget::AsNative, Value::Number(n)) => n.as_f64().map(ExtractedId::Numeric),
+        (ExtractTarget::AsNative, Value::Bool(b)) => Some(ExtractedId::Bool(*b)),
         _ => None,
     }
 }
 
+/// Extract ID value from JSONB document as a raw JSONB sub-value
+///
+/// Companion to [`jsonb_extract_id`] that avoids the lossy `to_string()`
+/// round-trip: the caller gets the value back as-is (object, array,
+/// number, bool, string) instead of forcing it through text.
+///
+/// # Arguments
+///
+/// * `data` - JSONB document containing the ID
+/// * `key` - Key to extract (default: 'id')
+///
+/// # Returns
+///
+/// The raw JSONB value at `key`, or NULL if the key is absent.
+///
+/// # Examples
+///
+/// ```sql
+/// SELECT jsonb_extract_id_jsonb('{"id": {"tenant": 1, "entity": 5}}'::jsonb);
+/// -- Returns: {"tenant": 1, "entity": 5}
+/// ```
+#[allow(clippy::needless_pass_by_value)]
+#[pg_extern(immutable, parallel_safe)]
+fn jsonb_extract_id_jsonb(data: JsonB, key: default!(&str, "'id'")) -> Option<JsonB> {
+    match extract_as(&data, key, ExtractTarget::AsJsonb)? {
+        ExtractedId::Jsonb(v) => Some(JsonB(v.clone())),
+        ExtractedId::Text(_) | ExtractedId::Numeric(_) | ExtractedId::Bool(_) => None,
+    }
+}
+
+/// Extract a numeric ID value from JSONB document as a native `f64`
+///
+/// Companion to [`jsonb_extract_id`] for `pg_tview` propagation logic that
+/// needs to compare numbers directly rather than as coerced text, without
+/// re-parsing the `to_string()` output. The value is returned as an `f64`,
+/// so integers wider than 53 bits come back rounded.
+///
+/// # Arguments
+///
+/// * `data` - JSONB document containing the ID
+/// * `key` - Key to extract (default: 'id')
+///
+/// # Returns
+///
+/// The numeric value at `key`, or NULL if the key is absent or not a number.
+///
+/// # Examples
+///
+/// ```sql
+/// SELECT jsonb_extract_numeric('{"id": 42}'::jsonb);
+/// -- Returns: 42
+///
+/// SELECT jsonb_extract_numeric('{"id": "not-a-number"}'::jsonb);
+/// -- Returns: NULL
+/// ```
+#[allow(clippy::needless_pass_by_value)]
+#[pg_extern(immutable, parallel_safe)]
+fn jsonb_extract_numeric(data: JsonB, key: default!(&str, "'id'")) -> Option<f64> {
+    match extract_as(&data, key, ExtractTarget::AsNative)? {
+        ExtractedId::Numeric(n) => Some(n),
+        ExtractedId::Text(_) | ExtractedId::Jsonb(_) | ExtractedId::Bool(_) => None,
+    }
+}
+
+/// Extract a boolean ID value from JSONB document
+///
+/// Companion to [`jsonb_extract_id`] for `pg_tview` propagation logic that
+/// needs booleans directly instead of coerced text.
+///
+/// # Arguments
+///
+/// * `data` - JSONB document containing the ID
+/// * `key` - Key to extract (default: 'id')
+///
+/// # Returns
+///
+/// The boolean value at `key`, or NULL if the key is absent or not a boolean.
+///
+/// # Examples
+///
+/// ```sql
+/// SELECT jsonb_extract_bool('{"id": true}'::jsonb);
+/// -- Returns: true
+/// ```
+#[allow(clippy::needless_pass_by_value)]
+#[pg_extern(immutable, parallel_safe)]
+fn jsonb_extract_bool(data: JsonB, key: default!(&str, "'id'")) -> Option<bool> {
+    match extract_as(&data, key, ExtractTarget::AsNative)? {
+        ExtractedId::Bool(b) => Some(b),
+        ExtractedId::Text(_) | ExtractedId::Jsonb(_) | ExtractedId::Numeric(_) => None,
+    }
+}
+
 /// Check if JSONB array contains element with specific ID
 ///
 /// Fast containment check for `pg_tview` implementations, with optimized
@@ -108,7 +240,10 @@ fn jsonb_extract_id(data: JsonB, key: default!(&str, "'id'")) -> Option<String>
 /// # Arguments
 ///
 /// * `data` - JSONB document containing the array
-/// * `array_path` - Path to array field (e.g., 'posts')
+/// * `array_path` - Path to array field. Supports a small JSONPath-like
+///   mini-language (see the [`path`] module): dotted fields (`data.posts`),
+///   fixed indices (`items[0].tags`), and wildcards over nested arrays
+///   (`groups[*].members`), in addition to a plain top-level key (`posts`).
 /// * `id_key` - Key to match on (e.g., 'id')
 /// * `id_value` - Value to search for
 ///
@@ -142,6 +277,24 @@ fn jsonb_extract_id(data: JsonB, key: default!(&str, "'id'")) -> Option<String>
 /// );
 /// -- Returns: true
 ///
+/// -- Nested array (data.posts instead of a top-level key)
+/// SELECT jsonb_array_contains_id(
+///     '{"data": {"posts": [{"id": 1}, {"id": 2}]}}'::jsonb,
+///     'data.posts',
+///     'id',
+///     '2'::jsonb
+/// );
+/// -- Returns: true
+///
+/// -- Array-of-arrays via wildcard
+/// SELECT jsonb_array_contains_id(
+///     '{"groups": [{"members": [{"id": 1}]}, {"members": [{"id": 2}]}]}'::jsonb,
+///     'groups[*].members',
+///     'id',
+///     '2'::jsonb
+/// );
+/// -- Returns: true
+///
 /// -- Not found
 /// SELECT jsonb_array_contains_id(
 ///     '{"posts": [{"id": 1}, {"id": 2}]}'::jsonb,
@@ -167,29 +320,222 @@ fn jsonb_extract_id(data: JsonB, key: default!(&str, "'id'")) -> Option<String>
 #[allow(clippy::needless_pass_by_value)]
 #[pg_extern(immutable, parallel_safe, strict)]
 fn jsonb_array_contains_id(data: JsonB, array_path: &str, id_key: &str, id_value: JsonB) -> bool {
-    let Some(obj) = data.0.as_object() else {
+    let segments = path::parse_path(array_path);
+
+    path::resolve_arrays(&data.0, &segments)
+        .into_iter()
+        .filter_map(Value::as_array)
+        .any(|array| find_element_by_match(array, id_key, &id_value.0).is_some())
+}
+
+/// Zero-copy variant of [`jsonb_array_contains_id`] for wide documents
+///
+/// Walks PostgreSQL's on-disk JSONB container bytes directly (see the
+/// [`raw_jsonb`] module) to seek to `array_path` and compare `id_key`
+/// without first deserializing the whole document into a
+/// `serde_json::Value` tree. Only plain dotted field paths are handled by
+/// the fast reader (no `[n]`/`[*]` segments); anything it doesn't
+/// recognize falls back to [`jsonb_array_contains_id`]'s fully-parsed
+/// path, so behavior is identical either way, just not always the same
+/// speed.
+///
+/// Bypassing the normal typed-argument conversion for `data` is the whole
+/// point (that's where the deserialization cost lives), so this function
+/// takes the raw `FunctionCallInfo` instead of a `JsonB` argument.
+///
+/// # Arguments
+///
+/// Same four arguments as `jsonb_array_contains_id`: `data`, `array_path`,
+/// `id_key`, `id_value`.
+///
+/// # Examples
+///
+/// ```sql
+/// SELECT jsonb_array_contains_id_fast(
+///     '{"posts": [{"id": 1}, {"id": 2}, {"id": 3}]}'::jsonb,
+///     'posts',
+///     'id',
+///     '2'::jsonb
+/// );
+/// -- Returns: true
+/// ```
+///
+/// # Safety
+///
+/// Relies on the stable `FunctionCallInfoBaseData` ABI to read arguments
+/// directly; only sound when called as a SQL-invoked `pg_extern` with
+/// exactly the four arguments declared above.
+///
+/// A Rust signature of only `fcinfo: pg_sys::FunctionCallInfo` has no
+/// typed parameters for pgrx to read, so the auto-generated `CREATE
+/// FUNCTION` would register this as zero-argument SQL and the call above
+/// would never reach a real document (`call.nargs` would be 0 and the
+/// `[data_arg, path_arg, key_arg, value_arg]` destructure below would
+/// always fail). Supplying `sql` here pins the catalog signature to the
+/// real 4-argument shape `jsonb_array_contains_id` also exposes, while the
+/// Rust body keeps reading `data` straight off the `Datum` instead of
+/// through the normal typed-argument conversion.
+#[pg_extern(sql = "
+CREATE FUNCTION \"jsonb_array_contains_id_fast\"(
+    \"data\" jsonb,
+    \"array_path\" TEXT,
+    \"id_key\" TEXT,
+    \"id_value\" jsonb
+) RETURNS bool
+STRICT IMMUTABLE PARALLEL SAFE
+LANGUAGE c
+AS 'MODULE_PATHNAME', 'jsonb_array_contains_id_fast_wrapper';
+")]
+unsafe fn jsonb_array_contains_id_fast(fcinfo: pg_sys::FunctionCallInfo) -> bool {
+    let call = &*fcinfo;
+    let args = call.args.as_slice(call.nargs as usize);
+    let [data_arg, path_arg, key_arg, value_arg] = args else {
         return false;
     };
 
-    let Some(array) = obj.get(array_path).and_then(|v| v.as_array()) else {
+    let (Some(array_path), Some(id_key)) = (
+        raw_jsonb::arg_str(path_arg.value, path_arg.isnull),
+        raw_jsonb::arg_str(key_arg.value, key_arg.isnull),
+    ) else {
         return false;
     };
 
-    // Use optimized search helper
-    find_element_by_match(array, id_key, &id_value.0).is_some()
+    let Some(id_value) = raw_jsonb::arg_jsonb(value_arg.value, value_arg.isnull) else {
+        return false;
+    };
+
+    if !data_arg.isnull {
+        if let Some(fast_result) =
+            raw_jsonb::try_contains_id_fast(data_arg.value, array_path, id_key, &id_value)
+        {
+            return fast_result;
+        }
+    }
+
+    // Fast reader declined (unsupported path segment, exotic numeric
+    // encoding, ...); fall back to the fully-parsed path.
+    let Some(data) = raw_jsonb::arg_jsonb(data_arg.value, data_arg.isnull) else {
+        return false;
+    };
+    let segments = path::parse_path(array_path);
+    path::resolve_arrays(&data, &segments)
+        .into_iter()
+        .filter_map(Value::as_array)
+        .any(|array| find_element_by_match(array, id_key, &id_value).is_some())
 }
 
-/// Find element in array by key-value match with integer optimization
+/// Find element in array by key-value match with numeric optimization
 #[inline]
 fn find_element_by_match(array: &[Value], match_key: &str, match_value: &Value) -> Option<usize> {
-    match_value.as_i64().map_or_else(
-        || {
-            array
-                .iter()
-                .position(|elem| elem.get(match_key).is_some_and(|v| v == match_value))
-        },
-        |int_id| crate::search::find_by_int_id_optimized(array, match_key, int_id),
-    )
+    if match_value.is_number() {
+        return crate::search::find_by_num_id_optimized(array, match_key, match_value);
+    }
+
+    array
+        .iter()
+        .position(|elem| elem.get(match_key).is_some_and(|v| v == match_value))
+}
+
+/// Check if JSONB array contains element matching every given key/value pair
+///
+/// Composite-key counterpart to [`jsonb_array_contains_id`] for event-sourced
+/// views keyed by more than one field (e.g. `{tenant_id, entity_id}`).
+///
+/// # Arguments
+///
+/// * `data` - JSONB document containing the array
+/// * `array_path` - Path to array field, same mini-language as
+///   [`jsonb_array_contains_id`] (dotted fields, `[n]` indices, `[*]` wildcards)
+/// * `keys` - Keys to match on, e.g. `ARRAY['tenant_id', 'entity_id']`
+/// * `values` - Values to match, positionally paired with `keys`
+///
+/// # Returns
+///
+/// true if array contains an element where every `(key, value)` pair
+/// matches; false (not an error) if `keys` and `values` have different
+/// lengths.
+///
+/// # Examples
+///
+/// ```sql
+/// SELECT jsonb_array_contains_keys(
+///     '{"rows": [{"tenant_id": 1, "entity_id": 5}, {"tenant_id": 1, "entity_id": 9}]}'::jsonb,
+///     'rows',
+///     ARRAY['tenant_id', 'entity_id'],
+///     ARRAY['1'::jsonb, '9'::jsonb]
+/// );
+/// -- Returns: true
+/// ```
+#[allow(clippy::needless_pass_by_value)]
+#[pg_extern(immutable, parallel_safe, strict)]
+fn jsonb_array_contains_keys(
+    data: JsonB,
+    array_path: &str,
+    keys: Vec<String>,
+    values: Vec<JsonB>,
+) -> bool {
+    if keys.len() != values.len() {
+        return false;
+    }
+
+    let pairs: Vec<(&str, &Value)> = keys
+        .iter()
+        .map(String::as_str)
+        .zip(values.iter().map(|v| &v.0))
+        .collect();
+
+    let segments = path::parse_path(array_path);
+
+    path::resolve_arrays(&data.0, &segments)
+        .into_iter()
+        .filter_map(Value::as_array)
+        .any(|array| find_element_by_matches(array, &pairs).is_some())
+}
+
+/// Find element in array matching every `(key, value)` pair.
+///
+/// If any pair has a numeric value, the first one is used to cheaply
+/// enumerate candidate positions via [`search::find_by_num_id_optimized`]
+/// (the same u64/float-widening scan `jsonb_array_contains_id` uses, so an
+/// integer key stored as a float like `42.0` is still enumerated instead
+/// of silently skipped); each candidate is then checked against the
+/// remaining pairs via [`matches_all`], and the scan resumes just past any
+/// candidate that fails, so every element is touched only when no element
+/// matches all pairs (or the match is the last element).
+#[inline]
+fn find_element_by_matches(array: &[Value], pairs: &[(&str, &Value)]) -> Option<usize> {
+    let Some((num_key, num_value)) = pairs.iter().find(|(_, v)| v.is_number()).copied() else {
+        return array.iter().position(|elem| matches_all(elem, pairs));
+    };
+
+    let mut offset = 0;
+    while let Some(rel) = crate::search::find_by_num_id_optimized(&array[offset..], num_key, num_value) {
+        let idx = offset + rel;
+        if matches_all(&array[idx], pairs) {
+            return Some(idx);
+        }
+        offset = idx + 1;
+    }
+    None
+}
+
+/// Check whether `elem` matches every `(key, value)` pair
+///
+/// Numeric pairs go through [`search::numeric_eq`] so composite-key
+/// matching gets the same u64/float widening as the single-key
+/// `jsonb_array_contains_id` path, instead of falling back to strict
+/// `Value` equality that would miss `42` against a stored `42.0`.
+#[inline]
+fn matches_all(elem: &Value, pairs: &[(&str, &Value)]) -> bool {
+    pairs.iter().all(|(k, v)| {
+        elem.get(*k).is_some_and(|elem_v| {
+            if v.is_number() {
+                elem_v.is_number() && crate::search::numeric_eq(elem_v, v)
+            } else {
+                elem_v == *v
+            }
+        })
+    })
 }
 
 /// Helper function to get human-readable type name for error messages
@@ -0,0 +1,101 @@
+// jsonb_delta - JSONPath Mini-Language Module
+//
+// Small path parser and evaluator for addressing arrays nested inside
+// sub-objects or arrays-of-arrays, modeled on the jsonb crate's
+// `get_by_path`. Used by `jsonb_array_contains_id` to resolve
+// `array_path` arguments deeper than a single top-level key.
+
+use serde_json::Value;
+
+/// A single step in a `JsonPath`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A plain object field, e.g. the `posts` in `data.posts`.
+    DotField(String),
+    /// A fixed array index, e.g. the `0` in `items[0]`.
+    Index(usize),
+    /// Every element of an array, e.g. the `*` in `groups[*]`.
+    Wildcard,
+}
+
+/// A parsed path, evaluated left-to-right against a `&Value`.
+pub type JsonPath = Vec<PathSegment>;
+
+/// Parse a path string such as `data.posts`, `items[0].tags`, or
+/// `groups[*].members` into a sequence of `PathSegment`s.
+///
+/// Dots separate object fields; `[n]` selects an array index; `[*]`
+/// expands every element of an array. Malformed bracket contents (not a
+/// non-negative integer and not `*`) are silently skipped, so the path
+/// resolves as though that bracket were absent (`posts[x]` acts like
+/// `posts`); an unclosed `[` ends parsing of that field.
+#[must_use]
+pub fn parse_path(path: &str) -> JsonPath {
+    let mut segments = Vec::new();
+    for field in path.split('.') {
+        parse_field(field, &mut segments);
+    }
+    segments
+}
+
+fn parse_field(field: &str, segments: &mut JsonPath) {
+    let name_end = field.find('[').unwrap_or(field.len());
+    let name = &field[..name_end];
+    if !name.is_empty() {
+        segments.push(PathSegment::DotField(name.to_string()));
+    }
+
+    let mut rest = &field[name_end..];
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find(']') else {
+            break;
+        };
+        let inner = &rest[open + 1..open + close];
+        if inner == "*" {
+            segments.push(PathSegment::Wildcard);
+        } else if let Ok(idx) = inner.parse::<usize>() {
+            segments.push(PathSegment::Index(idx));
+        }
+        rest = &rest[open + close + 1..];
+    }
+}
+
+/// Walk `value` along `path`, returning every array reached at the end of
+/// the path.
+///
+/// A missing field or a type mismatch at any segment simply drops that
+/// branch (no match), and `Wildcard` on a non-array is skipped rather than
+/// erroring. A `Wildcard` segment fans a single branch out into one per
+/// array element, so paths like `groups[*].members` can yield multiple
+/// candidate arrays, all of which are returned for the caller to search in
+/// turn.
+#[must_use]
+pub fn resolve_arrays<'a>(value: &'a Value, path: &[PathSegment]) -> Vec<&'a Value> {
+    let mut current: Vec<&Value> = vec![value];
+
+    for segment in path {
+        let mut next = Vec::new();
+        for v in current {
+            match segment {
+                PathSegment::DotField(field) => {
+                    if let Some(found) = v.as_object().and_then(|o| o.get(field)) {
+                        next.push(found);
+                    }
+                }
+                PathSegment::Index(idx) => {
+                    if let Some(found) = v.as_array().and_then(|a| a.get(*idx)) {
+                        next.push(found);
+                    }
+                }
+                PathSegment::Wildcard => {
+                    if let Some(arr) = v.as_array() {
+                        next.extend(arr.iter());
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+
+    current.into_iter().filter(|v| v.is_array()).collect()
+}
@@ -0,0 +1,799 @@
+// jsonb_delta - Zero-Copy Binary JSONB Reader
+//
+// Walks PostgreSQL's on-disk JSONB container format directly (header +
+// JEntry array, as laid out in `utils/jsonb.h`) so `jsonb_array_contains_id_fast`
+// can seek straight to `array_path` and scan `id_key` without
+// materializing the whole document into a `serde_json::Value` tree first.
+// Mirrors the get-by-path-vs-serde_json comparison that motivates binary
+// JSONB libraries in general.
+//
+// The byte-level parsing in this module is plain safe Rust over a
+// `&[u8]` container slice; the unsafe seams are `container_bytes`,
+// translating a detoasted `JsonB` datum into that slice, and the
+// `arg_str`/`arg_jsonb` readers for raw function arguments. Anything this
+// reader doesn't understand (compressed numerics it can't decode inline,
+// an unexpected entry layout) returns `None` so the caller falls back to
+// the existing serde-based [`crate::find_element_by_match`] path.
+//
+// Entries are always walked forward through `RawEntries`, which carries a
+// running byte cursor (mirroring PG's own `JsonbIteratorNext`) instead of
+// re-summing prefix offsets from index 0 on every lookup -- the latter
+// would make a single object `get` or array scan quadratic in the
+// element count, erasing the savings this module exists to capture.
+
+use pgrx::pg_sys;
+use serde_json::Value;
+
+/// Mask for the element/pair count in a `JsonbContainer` header.
+const JB_CMASK: u32 = 0x0FFF_FFFF;
+/// Header flag: container is a raw scalar (single-element pseudo-array).
+const JB_FSCALAR: u32 = 0x1000_0000;
+/// Header flag: container is an object.
+const JB_FOBJECT: u32 = 0x2000_0000;
+/// Header flag: container is an array.
+const JB_FARRAY: u32 = 0x4000_0000;
+
+/// Mask for a `JEntry`'s offset/length payload.
+const JENTRY_OFFLENMASK: u32 = 0x0FFF_FFFF;
+/// Mask for a `JEntry`'s type tag.
+const JENTRY_TYPEMASK: u32 = 0x7000_0000;
+/// Flag: this `JEntry`'s payload is an absolute end offset, not a length.
+const JENTRY_HAS_OFF: u32 = 0x8000_0000;
+
+const JENTRY_ISSTRING: u32 = 0x0000_0000;
+const JENTRY_ISNUMERIC: u32 = 0x1000_0000;
+const JENTRY_ISBOOL_FALSE: u32 = 0x2000_0000;
+const JENTRY_ISBOOL_TRUE: u32 = 0x3000_0000;
+const JENTRY_ISNULL: u32 = 0x4000_0000;
+const JENTRY_ISCONTAINER: u32 = 0x5000_0000;
+
+/// A value read straight out of a `JsonbContainer`'s backing bytes,
+/// without allocating (other than the unavoidable `&str`/slice borrows).
+#[derive(Debug, PartialEq)]
+enum RawValue<'a> {
+    String(&'a str),
+    /// Raw bytes of a PostgreSQL `Numeric` varlena, header included --
+    /// stripped by [`decode_numeric_i64`] itself via [`strip_varlena_header`].
+    Numeric(&'a [u8]),
+    Bool(bool),
+    Null,
+    Container(RawContainer<'a>),
+}
+
+/// A `JsonbContainer`'s header plus the `JEntry` array and trailing data,
+/// borrowed directly from the detoasted datum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RawContainer<'a> {
+    header: u32,
+    /// `JEntry`s: for objects, `2 * count` entries (keys then values); for
+    /// arrays, `count` entries; for scalars, exactly one entry.
+    entries: &'a [u8],
+    /// Key/value bytes following the `JEntry` array.
+    data: &'a [u8],
+    count: u32,
+}
+
+/// Decode the `JEntry` header + its already-resolved data slice into a
+/// [`RawValue`]. Free function (rather than a `RawContainer` method)
+/// because it only needs the one entry's bytes, not the container.
+fn decode_value(entry: u32, slice: &[u8]) -> Option<RawValue<'_>> {
+    match entry & JENTRY_TYPEMASK {
+        JENTRY_ISSTRING => std::str::from_utf8(slice).ok().map(RawValue::String),
+        JENTRY_ISNUMERIC => Some(RawValue::Numeric(slice)),
+        JENTRY_ISBOOL_FALSE => Some(RawValue::Bool(false)),
+        JENTRY_ISBOOL_TRUE => Some(RawValue::Bool(true)),
+        JENTRY_ISNULL => Some(RawValue::Null),
+        JENTRY_ISCONTAINER => parse_container(slice).map(RawValue::Container),
+        _ => None,
+    }
+}
+
+/// Round `offset` up to the next 4-byte boundary, the way PG's `INTALIGN`
+/// macro does.
+#[inline]
+fn intalign(offset: u32) -> u32 {
+    (offset + 3) & !3
+}
+
+/// Forward-only walk over a container's `JEntry` array that carries the
+/// running byte cursor across elements (mirrors PG's `JsonbIteratorNext`),
+/// so scanning `n` entries costs O(n) total rather than O(n) per entry.
+struct RawEntries<'a> {
+    container: RawContainer<'a>,
+    next_idx: u32,
+    cursor: u32,
+}
+
+impl<'a> Iterator for RawEntries<'a> {
+    type Item = (u32, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n_entries = (self.container.entries.len() / 4) as u32;
+        if self.next_idx >= n_entries {
+            return None;
+        }
+
+        let entry = self.container.entry_at(self.next_idx);
+        let field = entry & JENTRY_OFFLENMASK;
+        let end = if entry & JENTRY_HAS_OFF != 0 {
+            field
+        } else {
+            self.cursor + field
+        };
+
+        // Numeric and container payloads are written with a leading pad
+        // to a 4-byte boundary (see `fillJsonbValue` in PG's jsonb.c),
+        // and the JEntry's length field includes that padding. Strings,
+        // bools, and nulls carry no such pad.
+        let content_start = match entry & JENTRY_TYPEMASK {
+            JENTRY_ISNUMERIC | JENTRY_ISCONTAINER => intalign(self.cursor),
+            _ => self.cursor,
+        };
+        let slice = &self.container.data[content_start as usize..end as usize];
+
+        self.cursor = end;
+        self.next_idx += 1;
+        Some((entry, slice))
+    }
+}
+
+impl<'a> RawContainer<'a> {
+    fn is_object(self) -> bool {
+        self.header & JB_FOBJECT != 0
+    }
+
+    fn is_array(self) -> bool {
+        self.header & JB_FARRAY != 0 || self.header & JB_FSCALAR != 0
+    }
+
+    fn entry_at(self, idx: u32) -> u32 {
+        let start = idx as usize * 4;
+        u32::from_ne_bytes(self.entries[start..start + 4].try_into().unwrap())
+    }
+
+    fn iter_raw(self) -> RawEntries<'a> {
+        RawEntries {
+            container: self,
+            next_idx: 0,
+            cursor: 0,
+        }
+    }
+
+    /// Look up an object field by key without decoding sibling values.
+    ///
+    /// Walks keys in order via [`RawEntries`] (one forward pass, O(count)
+    /// worst case); once a match is found, skips directly to its paired
+    /// value rather than rescanning, using the fact that value `i` always
+    /// sits exactly `count - 1` steps after the key that preceded it in
+    /// the same forward pass.
+    fn get(self, key: &str) -> Option<RawValue<'a>> {
+        if !self.is_object() {
+            return None;
+        }
+
+        let mut entries = self.iter_raw();
+        for _ in 0..self.count {
+            let (entry, slice) = entries.next()?;
+            let RawValue::String(k) = decode_value(entry, slice)? else {
+                return None;
+            };
+            if k == key {
+                let (value_entry, value_slice) = entries.nth((self.count - 1) as usize)?;
+                return decode_value(value_entry, value_slice);
+            }
+        }
+        None
+    }
+}
+
+/// Parse a `JsonbContainer` header + `JEntry` array out of `bytes`.
+fn parse_container(bytes: &[u8]) -> Option<RawContainer<'_>> {
+    if bytes.len() < 4 {
+        return None;
+    }
+    let header = u32::from_ne_bytes(bytes[0..4].try_into().ok()?);
+    let count = header & JB_CMASK;
+
+    let is_object = header & JB_FOBJECT != 0;
+    let n_entries = if is_object { count * 2 } else { count.max(1) };
+    let entries_len = n_entries as usize * 4;
+    let entries_start = 4;
+    let entries_end = entries_start + entries_len;
+    if bytes.len() < entries_end {
+        return None;
+    }
+
+    Some(RawContainer {
+        header,
+        entries: &bytes[entries_start..entries_end],
+        data: &bytes[entries_end..],
+        count,
+    })
+}
+
+/// Sign/special tag occupying the top bits of a `numeric`'s on-disk
+/// header word, shared between the short and long on-disk forms.
+const NUMERIC_SIGN_MASK: u16 = 0xC000;
+const NUMERIC_POS: u16 = 0x0000;
+const NUMERIC_NEG: u16 = 0x4000;
+const NUMERIC_SHORT: u16 = 0x8000;
+const NUMERIC_SPECIAL: u16 = 0xC000;
+
+const NUMERIC_SHORT_SIGN_MASK: u16 = 0x2000;
+const NUMERIC_SHORT_DSCALE_MASK: u16 = 0x1F80;
+const NUMERIC_SHORT_DSCALE_SHIFT: u16 = 7;
+const NUMERIC_SHORT_WEIGHT_SIGN_MASK: u16 = 0x0040;
+const NUMERIC_SHORT_WEIGHT_MASK: u16 = 0x003F;
+
+/// Strip a `varlena` header (1-byte-header "short" form, or 4-byte-header
+/// uncompressed form) and return the header-stripped body.
+///
+/// A `Numeric` embedded in a JSONB container is always a plain in-memory
+/// value -- never TOASTed or compressed -- so those forms aren't handled
+/// here; encountering one returns `None` rather than misreading it.
+fn strip_varlena_header(bytes: &[u8]) -> Option<&[u8]> {
+    let first = *bytes.first()?;
+    if first & 0x01 == 0x00 {
+        // 4-byte header: the low 2 bits of the native-endian word are the
+        // variant tag (00 = plain uncompressed), and the total length
+        // (header included) is packed into the remaining 30 bits.
+        if bytes.len() < 4 {
+            return None;
+        }
+        let raw = u32::from_ne_bytes(bytes[0..4].try_into().ok()?);
+        if raw & 0x03 != 0x00 {
+            return None; // compressed 4B variant, not handled
+        }
+        let total_len = (raw >> 2) as usize;
+        bytes.get(4..total_len)
+    } else if first == 0x01 {
+        None // 1B-E: external/TOAST pointer, not a plain datum
+    } else {
+        // 1-byte header: total length (header included) in the top 7 bits.
+        let total_len = (first >> 1) as usize;
+        bytes.get(1..total_len)
+    }
+}
+
+/// Decode a PostgreSQL `Numeric` varlena to `i64`, if it fits.
+///
+/// Parses the real on-disk encoding: a varlena header, then the packed
+/// `NumericShort`/`NumericLong` header word (`ndigits` is derived from the
+/// varlena length, there's no separate length-prefixed field) followed by
+/// the base-10000 digit array. Handles zero-scale integers directly
+/// (including ones stored with trailing zero digits stripped, such as
+/// `10000` as digit `1` with weight `1`); anything fractional, `NaN`/`Inf`, or
+/// too wide for an `i64` returns `None`, signalling the caller to fall
+/// back to the serde-based path for that element.
+fn decode_numeric_i64(varlena: &[u8]) -> Option<i64> {
+    let body = strip_varlena_header(varlena)?;
+    if body.len() < 2 {
+        return None;
+    }
+    let n_header = u16::from_ne_bytes(body[0..2].try_into().ok()?);
+
+    let (sign, dscale, weight, digits_start) = match n_header & NUMERIC_SIGN_MASK {
+        NUMERIC_SHORT => {
+            let sign = if n_header & NUMERIC_SHORT_SIGN_MASK != 0 {
+                NUMERIC_NEG
+            } else {
+                NUMERIC_POS
+            };
+            let dscale = (n_header & NUMERIC_SHORT_DSCALE_MASK) >> NUMERIC_SHORT_DSCALE_SHIFT;
+            let mut weight = i32::from(n_header & NUMERIC_SHORT_WEIGHT_MASK);
+            if n_header & NUMERIC_SHORT_WEIGHT_SIGN_MASK != 0 {
+                weight |= !i32::from(NUMERIC_SHORT_WEIGHT_MASK);
+            }
+            (sign, dscale, weight, 2usize)
+        }
+        NUMERIC_SPECIAL => return None, // NaN / Inf / -Inf
+        _ => {
+            // Long form: a plain sign+dscale word followed by a signed weight.
+            if body.len() < 4 {
+                return None;
+            }
+            let sign = n_header & NUMERIC_SIGN_MASK;
+            let dscale = n_header & !NUMERIC_SIGN_MASK;
+            let weight = i32::from(i16::from_ne_bytes(body[2..4].try_into().ok()?));
+            (sign, dscale, weight, 4usize)
+        }
+    };
+
+    if dscale != 0 {
+        return None; // fractional: let the serde fallback handle it
+    }
+
+    let digit_bytes = &body[digits_start..];
+    let ndigits = digit_bytes.len() / 2;
+    if ndigits == 0 {
+        return Some(0);
+    }
+    if ndigits > 5 {
+        return None; // too large to fit an i64 safely via this fast path
+    }
+
+    let mut value: i64 = 0;
+    for i in 0..ndigits {
+        let d = i16::from_ne_bytes(digit_bytes[i * 2..i * 2 + 2].try_into().ok()?);
+        value = value.checked_mul(10_000)?.checked_add(i64::from(d))?;
+    }
+
+    let exponent = weight - (ndigits as i32 - 1);
+    if exponent < 0 {
+        return None; // digits below the decimal point
+    }
+    // Trailing zero digits are not stored; scale back up for each one.
+    for _ in 0..exponent {
+        value = value.checked_mul(10_000)?;
+    }
+
+    Some(if sign == NUMERIC_NEG { -value } else { value })
+}
+
+/// Borrow the detoasted container bytes of a `JsonB` datum.
+///
+/// # Safety
+///
+/// `datum` must be a valid, non-NULL `jsonb` `Datum` for the duration of
+/// the returned borrow (true for the lifetime of the calling `pg_extern`
+/// function's arguments).
+unsafe fn container_bytes<'a>(datum: pg_sys::Datum) -> &'a [u8] {
+    let detoasted = pg_sys::pg_detoast_datum_packed(datum.cast_mut_ptr());
+    let data = pg_sys::VARDATA_ANY(detoasted.cast());
+    let len = pg_sys::VARSIZE_ANY_EXHDR(detoasted.cast());
+    std::slice::from_raw_parts(data.cast::<u8>(), len)
+}
+
+/// Fast path behind [`crate::jsonb_array_contains_id_fast`]: resolve `array_path`
+/// and scan for `id_key == id_value` directly over the binary container,
+/// without building any `serde_json::Value`.
+///
+/// Returns `None` when the reader hits something it doesn't handle
+/// (nested path segments beyond plain dotted fields, a numeric ID it
+/// can't decode inline, etc.) so the caller can fall back to
+/// [`crate::find_element_by_match`] over the fully parsed document.
+///
+/// # Safety
+///
+/// `datum` must be a valid, non-NULL `jsonb` `Datum`.
+pub unsafe fn try_contains_id_fast(
+    datum: pg_sys::Datum,
+    array_path: &str,
+    id_key: &str,
+    id_value: &Value,
+) -> Option<bool> {
+    let bytes = container_bytes(datum);
+    let root = parse_container(bytes)?;
+
+    let mut current = root;
+    for field in array_path.split('.') {
+        if field.is_empty() || field.contains('[') {
+            return None; // empty fields/indices/wildcards: let the serde fallback handle it
+        }
+        let RawValue::Container(next) = current.get(field)? else {
+            return None;
+        };
+        current = next;
+    }
+
+    if !current.is_array() {
+        return None;
+    }
+
+    for (entry, slice) in current.iter_raw() {
+        let RawValue::Container(elem) = decode_value(entry, slice)? else {
+            continue;
+        };
+        let Some(field) = elem.get(id_key) else {
+            continue;
+        };
+        if raw_value_matches(&field, id_value)? {
+            return Some(true);
+        }
+    }
+    Some(false)
+}
+
+/// Read a text `Datum` as `&str` without going through the `#[pg_extern]`
+/// argument machinery (used to pull `array_path`/`id_key` out of a raw
+/// `FunctionCallInfo` in [`crate::jsonb_array_contains_id_fast`]).
+///
+/// # Safety
+///
+/// `datum`/`is_null` must describe a valid `text` argument for the
+/// lifetime of the borrow.
+pub unsafe fn arg_str<'a>(datum: pg_sys::Datum, is_null: bool) -> Option<&'a str> {
+    <&str as pgrx::datum::FromDatum>::from_polymorphic_datum(datum, is_null, pg_sys::TEXTOID)
+}
+
+/// Read a `jsonb` `Datum` as a `serde_json::Value`, fully deserializing
+/// it. Used for the small `id_value` argument, and for `data` only on the
+/// fallback path, once the raw reader has given up.
+///
+/// # Safety
+///
+/// `datum`/`is_null` must describe a valid `jsonb` argument for the
+/// lifetime of the call.
+pub unsafe fn arg_jsonb(datum: pg_sys::Datum, is_null: bool) -> Option<Value> {
+    <pgrx::JsonB as pgrx::datum::FromDatum>::from_polymorphic_datum(datum, is_null, pg_sys::JSONBOID)
+        .map(|j| j.0)
+}
+
+/// Compare a raw field value against the target `id_value`, returning
+/// `None` (rather than `Some(false)`) when the comparison can't be done
+/// without falling back to serde (e.g. an un-decodable large numeric).
+fn raw_value_matches(field: &RawValue<'_>, id_value: &Value) -> Option<bool> {
+    match (field, id_value) {
+        (RawValue::String(s), Value::String(target)) => Some(*s == target),
+        (RawValue::Bool(b), Value::Bool(target)) => Some(b == target),
+        (RawValue::Null, Value::Null) => Some(true),
+        (RawValue::Numeric(bytes), Value::Number(_)) => {
+            let raw_int = decode_numeric_i64(bytes)?;
+            let target_int = id_value.as_i64()?;
+            Some(raw_int == target_int)
+        }
+        (RawValue::Container(_), Value::Array(_) | Value::Object(_)) => None,
+        _ => Some(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_numeric_i64, parse_container, raw_value_matches, RawValue, NUMERIC_SHORT,
+        NUMERIC_SHORT_SIGN_MASK, NUMERIC_SHORT_WEIGHT_MASK, NUMERIC_SHORT_WEIGHT_SIGN_MASK,
+    };
+    use serde_json::Value;
+
+    const JENTRY_ISSTRING: u32 = 0x0000_0000;
+    const JENTRY_ISNUMERIC: u32 = 0x1000_0000;
+    const JENTRY_ISBOOL_TRUE: u32 = 0x3000_0000;
+    const JENTRY_ISCONTAINER: u32 = 0x5000_0000;
+    const JENTRY_OFFLENMASK: u32 = 0x0FFF_FFFF;
+    const JENTRY_TYPEMASK: u32 = 0x7000_0000;
+    const JENTRY_HAS_OFF: u32 = 0x8000_0000;
+    const JB_FOBJECT: u32 = 0x2000_0000;
+    const JB_FARRAY: u32 = 0x4000_0000;
+
+    /// Encode `value` as a genuine on-disk short-form PostgreSQL `numeric`
+    /// varlena: a 1-byte varlena header followed by the packed
+    /// `NumericShort` header word and base-10000 digits -- the same
+    /// format `decode_numeric_i64` parses, used here so the tests exercise
+    /// the real encoding rather than an invented struct layout.
+    fn encode_short_numeric(value: i64) -> Vec<u8> {
+        let mut digits = Vec::new();
+        let mut magnitude = value.unsigned_abs();
+        while magnitude > 0 {
+            digits.push((magnitude % 10_000) as i16);
+            magnitude /= 10_000;
+        }
+        digits.reverse();
+
+        let weight: i32 = if digits.is_empty() {
+            0
+        } else {
+            digits.len() as i32 - 1
+        };
+        let sign_bit = if value < 0 { NUMERIC_SHORT_SIGN_MASK } else { 0 };
+        let weight_bits = (weight as u16) & NUMERIC_SHORT_WEIGHT_MASK;
+        let weight_sign_bit = if weight < 0 {
+            NUMERIC_SHORT_WEIGHT_SIGN_MASK
+        } else {
+            0
+        };
+        let n_header = NUMERIC_SHORT | sign_bit | weight_sign_bit | weight_bits;
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&n_header.to_ne_bytes());
+        for d in &digits {
+            body.extend_from_slice(&d.to_ne_bytes());
+        }
+
+        let total_len = 1 + body.len(); // 1-byte varlena header + body
+        assert!(total_len <= 0x7F, "fixture too large for a 1-byte header");
+        let mut out = Vec::with_capacity(total_len);
+        out.push(((total_len as u8) << 1) | 0x01);
+        out.extend_from_slice(&body);
+        out
+    }
+
+    /// Encode `value` as a long-form (4-byte header) numeric varlena, to
+    /// exercise the less common on-disk variant too.
+    fn encode_long_numeric(value: i64) -> Vec<u8> {
+        let mut digits = Vec::new();
+        let mut magnitude = value.unsigned_abs();
+        while magnitude > 0 {
+            digits.push((magnitude % 10_000) as i16);
+            magnitude /= 10_000;
+        }
+        digits.reverse();
+        let weight: i16 = if digits.is_empty() {
+            0
+        } else {
+            digits.len() as i16 - 1
+        };
+        let sign: u16 = if value < 0 { 0x4000 } else { 0x0000 };
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&sign.to_ne_bytes()); // n_sign_dscale (dscale=0)
+        body.extend_from_slice(&weight.to_ne_bytes());
+        for d in &digits {
+            body.extend_from_slice(&d.to_ne_bytes());
+        }
+
+        let total_len = 4 + body.len(); // 4-byte varlena header + body
+        let header = (total_len as u32) << 2; // tag (low 2 bits) = 0: plain, uncompressed
+        let mut out = Vec::new();
+        out.extend_from_slice(&header.to_ne_bytes());
+        out.extend_from_slice(&body);
+        out
+    }
+
+    /// A field to be packed into a test object/array, paired with the
+    /// `JEntry` type tag it should carry.
+    fn push_field(data: &mut Vec<u8>, bytes: &[u8], tag: u32) -> u32 {
+        let needs_align = matches!(tag, JENTRY_ISNUMERIC | JENTRY_ISCONTAINER);
+        let pad = if needs_align {
+            (4 - (data.len() as u32 % 4)) % 4
+        } else {
+            0
+        };
+        for _ in 0..pad {
+            data.push(0);
+        }
+        data.extend_from_slice(bytes);
+        ((pad + bytes.len() as u32) & JENTRY_OFFLENMASK) | tag
+    }
+
+    /// Byte-for-byte `JsonbContainer` builder mirroring PG's own encoder
+    /// closely enough to round-trip through our reader: lays out the
+    /// header, `JEntry` array (keys then values, for objects), and the
+    /// key/value byte stream -- padding numeric/container values to a
+    /// 4-byte boundary the way `fillJsonbValue` expects, and optionally
+    /// marking the *last* value entry as a `JENTRY_HAS_OFF` resync point
+    /// so both entry styles get exercised.
+    fn build_object(pairs: &[(&str, &[u8], u32)], force_has_off_on_last_value: bool) -> Vec<u8> {
+        let count = pairs.len() as u32;
+        let mut data = Vec::new();
+        let mut fields = Vec::new();
+
+        for (k, _, _) in pairs {
+            fields.push(push_field(&mut data, k.as_bytes(), JENTRY_ISSTRING));
+        }
+        for (_, v, tag) in pairs {
+            fields.push(push_field(&mut data, v, *tag));
+        }
+
+        if force_has_off_on_last_value {
+            let last = fields.len() - 1;
+            let tag = fields[last] & JENTRY_TYPEMASK;
+            let end_offset = data.len() as u32;
+            fields[last] = (end_offset & JENTRY_OFFLENMASK) | tag | JENTRY_HAS_OFF;
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(&(count | JB_FOBJECT).to_ne_bytes());
+        for field in fields {
+            out.extend_from_slice(&field.to_ne_bytes());
+        }
+        out.extend_from_slice(&data);
+        out
+    }
+
+    /// Same as [`build_object`] but for a plain JSON array.
+    fn build_array(elems: &[(&[u8], u32)]) -> Vec<u8> {
+        let count = elems.len() as u32;
+        let mut data = Vec::new();
+        let mut fields = Vec::new();
+        for (v, tag) in elems {
+            fields.push(push_field(&mut data, v, *tag));
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(&(count | JB_FARRAY).to_ne_bytes());
+        for field in fields {
+            out.extend_from_slice(&field.to_ne_bytes());
+        }
+        out.extend_from_slice(&data);
+        out
+    }
+
+    #[test]
+    fn object_get_resolves_keys_in_any_position() {
+        let numeric = encode_short_numeric(987);
+        let pairs: Vec<(&str, &[u8], u32)> = vec![
+            ("tenant_id", &numeric, JENTRY_ISNUMERIC),
+            ("name", b"alice", JENTRY_ISSTRING),
+            ("active", b"", JENTRY_ISBOOL_TRUE),
+        ];
+
+        for force_off in [false, true] {
+            let bytes = build_object(&pairs, force_off);
+            let container = parse_container(&bytes).unwrap();
+
+            assert_eq!(container.get("tenant_id"), Some(RawValue::Numeric(&numeric)));
+            assert_eq!(container.get("name"), Some(RawValue::String("alice")));
+            assert_eq!(container.get("active"), Some(RawValue::Bool(true)));
+            assert_eq!(container.get("missing"), None);
+        }
+    }
+
+    #[test]
+    fn object_get_large_object_every_key() {
+        let owned: Vec<String> = (0..20).map(|i| format!("key{i}")).collect();
+        let pairs: Vec<(&str, &[u8], u32)> =
+            owned.iter().map(|k| (k.as_str(), b"abc".as_slice(), JENTRY_ISSTRING)).collect();
+        let bytes = build_object(&pairs, false);
+        let container = parse_container(&bytes).unwrap();
+        for k in &owned {
+            assert_eq!(container.get(k), Some(RawValue::String("abc")), "key {k}");
+        }
+        assert_eq!(container.get("nope"), None);
+    }
+
+    #[test]
+    fn object_get_numeric_after_odd_length_key_is_aligned() {
+        // "child" is 5 bytes, so the numeric that follows needs a 3-byte
+        // pad before it to land on a 4-byte boundary -- exercises the
+        // INTALIGN path end to end, not just in isolation.
+        let numeric = encode_short_numeric(42);
+        let pairs: Vec<(&str, &[u8], u32)> = vec![("child", &numeric, JENTRY_ISNUMERIC)];
+        let bytes = build_object(&pairs, false);
+        let container = parse_container(&bytes).unwrap();
+        let Some(RawValue::Numeric(got)) = container.get("child") else {
+            panic!("expected a numeric value");
+        };
+        assert_eq!(decode_numeric_i64(got), Some(42));
+    }
+
+    #[test]
+    fn nested_container_after_odd_length_key_is_aligned() {
+        let numeric = encode_short_numeric(7);
+        let inner = build_object(&[("id", &numeric, JENTRY_ISNUMERIC)], false);
+        let outer = build_object(&[("child", &inner, JENTRY_ISCONTAINER)], false);
+        let outer_c = parse_container(&outer).unwrap();
+        let Some(RawValue::Container(child)) = outer_c.get("child") else {
+            panic!("expected a nested container");
+        };
+        let Some(RawValue::Numeric(got)) = child.get("id") else {
+            panic!("expected a numeric value");
+        };
+        assert_eq!(decode_numeric_i64(got), Some(7));
+    }
+
+    #[test]
+    fn array_iter_raw_walks_elements_in_order() {
+        let elems: Vec<(&[u8], u32)> = vec![
+            (b"one".as_slice(), JENTRY_ISSTRING),
+            (b"two".as_slice(), JENTRY_ISSTRING),
+            (b"three".as_slice(), JENTRY_ISSTRING),
+        ];
+        let bytes = build_array(&elems);
+        let arr = parse_container(&bytes).unwrap();
+        let collected: Vec<_> = arr
+            .iter_raw()
+            .map(|(entry, slice)| super::decode_value(entry, slice))
+            .collect();
+        assert_eq!(collected[0], Some(RawValue::String("one")));
+        assert_eq!(collected[1], Some(RawValue::String("two")));
+        assert_eq!(collected[2], Some(RawValue::String("three")));
+    }
+
+    #[test]
+    fn array_element_numeric_after_string_is_aligned() {
+        let numeric = encode_short_numeric(1234);
+        let elems: Vec<(&[u8], u32)> = vec![
+            (b"abc".as_slice(), JENTRY_ISSTRING), // odd length -> forces padding
+            (&numeric, JENTRY_ISNUMERIC),
+        ];
+        let bytes = build_array(&elems);
+        let arr = parse_container(&bytes).unwrap();
+        let decoded: Vec<_> = arr
+            .iter_raw()
+            .map(|(entry, slice)| super::decode_value(entry, slice))
+            .collect();
+        assert_eq!(decoded[0], Some(RawValue::String("abc")));
+        let Some(RawValue::Numeric(got)) = &decoded[1] else {
+            panic!("expected a numeric value");
+        };
+        assert_eq!(decode_numeric_i64(got), Some(1234));
+    }
+
+    #[test]
+    fn decode_numeric_i64_round_trips_real_on_disk_encoding() {
+        for value in [0_i64, 1, 42, -42, 9_999, 10_000, 123_456_789, -123_456_789] {
+            let short = encode_short_numeric(value);
+            assert_eq!(decode_numeric_i64(&short), Some(value), "short form {value}");
+
+            let long = encode_long_numeric(value);
+            assert_eq!(decode_numeric_i64(&long), Some(value), "long form {value}");
+        }
+    }
+
+    #[test]
+    fn decode_numeric_i64_rejects_fractional_and_malformed() {
+        // A short-form numeric with a non-zero dscale (1.5 style value)
+        // bails out to the serde fallback instead of truncating.
+        let mut fractional = encode_short_numeric(15);
+        let dscale_bits = 1u16 << super::NUMERIC_SHORT_DSCALE_SHIFT;
+        let mut header = u16::from_ne_bytes(fractional[1..3].try_into().unwrap());
+        header |= dscale_bits;
+        fractional[1..3].copy_from_slice(&header.to_ne_bytes());
+        assert_eq!(decode_numeric_i64(&fractional), None);
+
+        // Too short to even have a varlena header.
+        assert_eq!(decode_numeric_i64(&[]), None);
+        assert_eq!(decode_numeric_i64(&[0]), None);
+
+        // A 1B-E (external/TOAST pointer) tag is never a plain datum here.
+        assert_eq!(decode_numeric_i64(&[0x01, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn raw_value_matches_numeric_via_decode() {
+        let seven = encode_short_numeric(7);
+        let field = RawValue::Numeric(&seven);
+        assert_eq!(raw_value_matches(&field, &Value::from(7)), Some(true));
+        assert_eq!(raw_value_matches(&field, &Value::from(8)), Some(false));
+        assert_eq!(raw_value_matches(&field, &Value::from("7")), Some(false));
+    }
+}
+
+/// Benchmark comparing [`crate::jsonb_array_contains_id_fast`] (this
+/// module) against the fully-parsed [`crate::jsonb_array_contains_id`] it
+/// falls back to, over a document wide enough that the deserialization
+/// cost `try_contains_id_fast` is meant to skip actually dominates.
+///
+/// Not a `criterion` bench: this crate has no Cargo.toml of its own to
+/// wire one into, and exercising the SQL-facing functions means going
+/// through a real PostgreSQL backend anyway, so a `#[pg_test]` timed with
+/// `Instant` (run via `cargo pgrx test`, the same harness the `pg_test`
+/// module at the crate root sets up) is the idiomatic fit here.
+#[cfg(test)]
+#[pgrx::pg_schema]
+mod perf_bench {
+    use pgrx::prelude::*;
+
+    #[pg_test]
+    fn bench_fast_path_vs_serde_path() {
+        Spi::connect(|mut client| {
+            client
+                .update(
+                    "CREATE TEMP TABLE bench_doc AS
+                     SELECT jsonb_build_object(
+                         'posts',
+                         (SELECT jsonb_agg(jsonb_build_object('id', g))
+                          FROM generate_series(1, 20000) g)
+                     ) AS doc",
+                    None,
+                    &[],
+                )
+                .unwrap();
+
+            let start = std::time::Instant::now();
+            client
+                .select(
+                    "SELECT jsonb_array_contains_id(doc, 'posts', 'id', '19999'::jsonb) FROM bench_doc",
+                    None,
+                    &[],
+                )
+                .unwrap();
+            let serde_elapsed = start.elapsed();
+
+            let start = std::time::Instant::now();
+            client
+                .select(
+                    "SELECT jsonb_array_contains_id_fast(doc, 'posts', 'id', '19999'::jsonb) FROM bench_doc",
+                    None,
+                    &[],
+                )
+                .unwrap();
+            let fast_elapsed = start.elapsed();
+
+            eprintln!(
+                "jsonb_array_contains_id (serde): {serde_elapsed:?}  vs  \
+                 jsonb_array_contains_id_fast (raw): {fast_elapsed:?}"
+            );
+        });
+    }
+}
@@ -62,3 +62,98 @@ pub fn find_by_int_id_scalar(array: &[Value], match_key: &str, match_value: i64)
         elem.get(match_key).and_then(serde_json::Value::as_i64) == Some(match_value)
     })
 }
+
+/// Canonical numeric equality, widening through i64 -> u64 -> f64.
+///
+/// Follows the numeric type-priority order used by JSON search engines:
+/// two numbers match if they resolve to the same integer under a common
+/// representation (covers Snowflake-style u64 IDs above `i64::MAX`), or if
+/// widening an integer to f64 compares bit-equal against the other side's
+/// f64 *and* that widened float round-trips back to the original integer
+/// exactly (guards against precision loss, so large integers don't spuriously
+/// match a nearby float).
+#[inline]
+pub(crate) fn numeric_eq(a: &Value, b: &Value) -> bool {
+    if let (Some(x), Some(y)) = (a.as_i64(), b.as_i64()) {
+        return x == y;
+    }
+    if let (Some(x), Some(y)) = (a.as_u64(), b.as_u64()) {
+        return x == y;
+    }
+
+    let (Some(af), Some(bf)) = (a.as_f64(), b.as_f64()) else {
+        return false;
+    };
+    if af.to_bits() != bf.to_bits() {
+        return false;
+    }
+
+    if let Some(i) = a.as_i64() {
+        return (i as f64) as i64 == i;
+    }
+    if let Some(u) = a.as_u64() {
+        return (u as f64) as u64 == u;
+    }
+    if let Some(i) = b.as_i64() {
+        return (i as f64) as i64 == i;
+    }
+    if let Some(u) = b.as_u64() {
+        return (u as f64) as u64 == u;
+    }
+    true
+}
+
+/// Optimized numeric ID matching covering u64 and float IDs, with the same
+/// loop-unrolling strategy as [`find_by_int_id_optimized`].
+///
+/// Unlike the i64-only path, this normalizes both sides through
+/// [`numeric_eq`] so large integer IDs and fractional IDs (e.g. `42` vs
+/// `42.0`) are compared correctly instead of falling back to slow
+/// `Value == Value` equality.
+#[inline]
+pub fn find_by_num_id_optimized(
+    array: &[Value],
+    match_key: &str,
+    match_value: &Value,
+) -> Option<usize> {
+    const UNROLL: usize = 8;
+
+    if array.len() < 32 {
+        return find_by_num_id_scalar(array, match_key, match_value);
+    }
+    let chunks = array.len() / UNROLL;
+
+    for chunk_idx in 0..chunks {
+        let base = chunk_idx * UNROLL;
+
+        for i in 0..UNROLL {
+            if let Some(v) = array[base + i].get(match_key) {
+                if numeric_eq(v, match_value) {
+                    return Some(base + i);
+                }
+            }
+        }
+    }
+
+    for (i, elem) in array.iter().enumerate().skip(chunks * UNROLL) {
+        if let Some(v) = elem.get(match_key) {
+            if numeric_eq(v, match_value) {
+                return Some(i);
+            }
+        }
+    }
+
+    None
+}
+
+/// Scalar fallback used by [`find_by_num_id_optimized`] for arrays of fewer than 32 elements
+#[inline]
+pub fn find_by_num_id_scalar(
+    array: &[Value],
+    match_key: &str,
+    match_value: &Value,
+) -> Option<usize> {
+    array
+        .iter()
+        .position(|elem| elem.get(match_key).is_some_and(|v| numeric_eq(v, match_value)))
+}